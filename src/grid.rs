@@ -1,59 +1,185 @@
 //! Module exposing the API for creating and interacting with a grid of cells.
 //!
-use crate::Object;
-/// Struct representing a simple cell on a grid. When initialising
-/// a [`Grid`], the neighbour indices for each cell are pre-calculated.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Cell {
-    neighbour_indices: Vec<(usize, usize)>,
-    pub alive: bool,
+use crate::{Object, Rule};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// The boundary condition applied when locating a cell's neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Boundary {
+    /// Cells outside the grid are treated as permanently dead; edge cells simply have fewer
+    /// neighbours. This is the crate's original, non-expanding behaviour.
+    #[default]
+    Dead,
+    /// The grid wraps around: a neighbour coordinate off the left edge wraps to the right edge
+    /// (and likewise for the other edges), so patterns like gliders can travel off one side and
+    /// reappear on the opposite side.
+    Toroidal,
 }
 
-impl Cell {
-    fn new() -> Self {
-        Self {
-            alive: false,
-            neighbour_indices: vec![],
-        }
+/// The main struct provided by this crate. A grid contains `width * height` cells of state `T`
+/// (defaulting to `bool`, for ordinary two-state automata like Conway's Game of Life), stored as
+/// a single flat, row-major buffer indexed by `y * width + x`, rather than one heap-allocated
+/// `Vec` per row. [`Grid::step_with`] (and the Conway-specific [`Grid::advance`]) swap between
+/// this buffer and a scratch buffer of the same size instead of cloning the whole grid every
+/// generation.
+#[derive(Debug)]
+pub struct Grid<T = bool> {
+    pub width: u32,
+    pub height: u32,
+    cells: Vec<T>,
+    scratch: Vec<T>,
+    boundary: Boundary,
+    rule: Rule,
+}
+
+impl<T> Grid<T> {
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width as usize + x
+    }
+
+    fn coords(&self, index: usize) -> (usize, usize) {
+        let width = self.width as usize;
+        (index % width, index / width)
     }
-    fn neighbour_count(&self, cells: &Vec<Vec<Cell>>) -> u32 {
-        let mut neighbour_count = 0;
-        for &position in &self.neighbour_indices {
-            neighbour_count += cells[position.1][position.0].alive as u32;
+
+    /// Returns a mutable reference to the cell at `(x, y)`, or `None` if `(x, y)` is out of
+    /// bounds.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width as usize && y < self.height as usize {
+            let idx = self.index(x, y);
+            Some(&mut self.cells[idx])
+        } else {
+            None
         }
+    }
 
-        neighbour_count
+    /// Sets the state of the cell at `(x, y)`. Returns `false` (and does nothing) if `(x, y)` is
+    /// out of bounds, `true` otherwise.
+    pub fn set(&mut self, x: usize, y: usize, state: T) -> bool {
+        match self.get_mut(x, y) {
+            Some(cell) => {
+                *cell = state;
+                true
+            }
+            None => false,
+        }
     }
 }
 
-/// The main struct provided by this crate. A grid contains many [`Cell`]s,
-/// each of which can be alive or dead.
-#[derive(Debug)]
-pub struct Grid {
-    pub width: u32,
-    pub height: u32,
-    pub cells: Vec<Vec<Cell>>,
+impl<T: Copy> Grid<T> {
+    /// Returns the state of the cell at `(x, y)`, or `None` if `(x, y)` is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        if x < self.width as usize && y < self.height as usize {
+            Some(self.cells[self.index(x, y)])
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over every cell in the grid, yielding its `(x, y)` coordinate
+    /// alongside its state.
+    pub fn indexed_cells(&self) -> impl Iterator<Item = ((usize, usize), T)> + '_ {
+        let width = self.width as usize;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, &state)| ((i % width, i / width), state))
+    }
 }
 
-impl Grid {
-    /// Initialise a new grid. Use this instead of manually creating a new instance,
-    /// as this function will pre-calculate the neighboiur indices for each cell.
-    pub fn new(width: u32, height: u32) -> Self {
-        let mut cells = vec![];
-        for _ in 0..height {
-            let mut row = vec![];
-            for _ in 0..width {
-                row.push(Cell::new())
+impl<T: Clone> Grid<T> {
+    /// Initialise a new grid with a dead (non-wrapping) boundary, seeding each cell's initial
+    /// state from `generator`.
+    pub fn with_generator(width: u32, height: u32, generator: impl Fn((usize, usize)) -> T) -> Self {
+        let mut cells = Vec::with_capacity((width * height) as usize);
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                cells.push(generator((x, y)));
             }
-            cells.push(row);
         }
-        let mut grid = Self {
+        let scratch = cells.clone();
+
+        Self {
             width,
             height,
             cells,
-        };
+            scratch,
+            boundary: Boundary::default(),
+            rule: Rule::default(),
+        }
+    }
 
-        grid.compute_neighbour_indices();
+    /// Returns the states of the (up to 8) in-bounds neighbours of `(x, y)`, according to the
+    /// grid's [`Boundary`] condition. Under [`Boundary::Toroidal`] on a grid with `width <= 2` or
+    /// `height <= 2`, some of the 8 offsets wrap onto the same physical cell as another offset;
+    /// each such cell is only counted once.
+    fn neighbours(&self, x: usize, y: usize) -> Vec<T> {
+        let width = self.width as isize;
+        let height = self.height as isize;
+        let (x, y) = (x as isize, y as isize);
+
+        let mut seen: Vec<(isize, isize)> = Vec::with_capacity(8);
+        let mut neighbours = Vec::with_capacity(8);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let (nx, ny) = match self.boundary {
+                    Boundary::Dead => (x + dx, y + dy),
+                    Boundary::Toroidal => ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height)),
+                };
+                if nx >= 0 && nx < width && ny >= 0 && ny < height && !seen.contains(&(nx, ny)) {
+                    seen.push((nx, ny));
+                    neighbours.push(self.cells[self.index(nx as usize, ny as usize)].clone());
+                }
+            }
+        }
+        neighbours
+    }
+
+    /// Advance the grid by one generation, replacing each cell's state with
+    /// `f(current_state, neighbour_states)`.
+    pub fn step_with(&mut self, f: impl Fn(&T, &[T]) -> T) {
+        for y in 0..self.height as usize {
+            for x in 0..self.width as usize {
+                let idx = self.index(x, y);
+                let neighbours = self.neighbours(x, y);
+                self.scratch[idx] = f(&self.cells[idx], &neighbours);
+            }
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.scratch);
+    }
+}
+
+impl Grid<bool> {
+    /// Initialise a new grid, with all cells dead, evolving under Conway's Game of Life rule
+    /// with a dead (non-wrapping) boundary.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::with_rule_and_boundary(width, height, Rule::default(), Boundary::default())
+    }
+
+    /// Initialise a new grid, with all cells dead, evolving under the given [`Rule`] with a dead
+    /// (non-wrapping) boundary.
+    pub fn with_rule(width: u32, height: u32, rule: Rule) -> Self {
+        Self::with_rule_and_boundary(width, height, rule, Boundary::default())
+    }
+
+    /// Initialise a new grid, with all cells dead, evolving under Conway's Game of Life rule
+    /// with the given [`Boundary`] condition.
+    pub fn with_boundary(width: u32, height: u32, boundary: Boundary) -> Self {
+        Self::with_rule_and_boundary(width, height, Rule::default(), boundary)
+    }
+
+    /// Initialise a new grid, with all cells dead, evolving under the given [`Rule`] and
+    /// [`Boundary`] condition.
+    pub fn with_rule_and_boundary(width: u32, height: u32, rule: Rule, boundary: Boundary) -> Self {
+        let mut grid = Self::with_generator(width, height, |_| false);
+        grid.rule = rule;
+        grid.boundary = boundary;
         grid
     }
 
@@ -61,97 +187,112 @@ impl Grid {
     /// for debugging purposes
     pub fn print_alive_cells(&self) {
         println!("------- Alive cells ---------");
-        for (y, row) in self.cells.iter().enumerate() {
-            for (x, cell) in row.iter().enumerate() {
-                if cell.alive {
-                    print!("({x}, {y}), ");
-                }
+        for (i, &alive) in self.cells.iter().enumerate() {
+            if alive {
+                let (x, y) = self.coords(i);
+                print!("({x}, {y}), ");
             }
         }
         println!("-----------------------------");
     }
 
-    /// Advance the grid by one generation.
+    /// Advance the grid by one generation, according to its [`Rule`]. Counts each cell's live
+    /// neighbours arithmetically rather than going through [`Grid::step_with`]/[`Grid::neighbours`],
+    /// so that advancing the primary bool grid stays free of the per-cell `Vec` allocation those
+    /// generic paths need to support an arbitrary `T`.
     pub fn advance(&mut self) {
-        let old_cells = self.cells.clone();
-        for row in self.cells.iter_mut() {
-            for cell in row {
-                match cell.neighbour_count(&old_cells) {
-                    0..=1 => {
-                        cell.alive = false;
-                    }
-                    2 => {}
-                    3 => {
-                        cell.alive = true;
-                    }
-                    4.. => {
-                        cell.alive = false;
-                    }
-                };
-            }
-        }
-    }
-
-    fn compute_neighbour_indices(&mut self) {
-        for (y, row) in self.cells.iter_mut().enumerate() {
-            for (x, cell) in row.iter_mut().enumerate() {
-                let mut x_indices = vec![x];
-                let mut y_indices = vec![y];
+        let width = self.width as isize;
+        let height = self.height as isize;
 
-                if x != 0 {
-                    let i = x - 1;
-                    if self.width > i as u32 {
-                        x_indices.push(i);
+        for y in 0..height {
+            for x in 0..width {
+                // Under a toroidal boundary on a grid with width <= 2 or height <= 2, two
+                // offsets can wrap onto the same physical cell; track which cells have already
+                // been counted (on the stack, to keep this loop allocation-free) so such a cell
+                // is only counted once.
+                let mut seen = [(isize::MIN, isize::MIN); 8];
+                let mut neighbour_count = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if dx == 0 && dy == 0 {
+                            continue;
+                        }
+                        let (nx, ny) = match self.boundary {
+                            Boundary::Dead => (x + dx, y + dy),
+                            Boundary::Toroidal => {
+                                ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height))
+                            }
+                        };
+                        if nx >= 0
+                            && nx < width
+                            && ny >= 0
+                            && ny < height
+                            && !seen[..neighbour_count].contains(&(nx, ny))
+                            && self.cells[self.index(nx as usize, ny as usize)]
+                        {
+                            seen[neighbour_count] = (nx, ny);
+                            neighbour_count += 1;
+                        }
                     }
                 }
 
-                if x != self.width as usize - 1 {
-                    let i = x + 1;
-                    if self.width > i as u32 {
-                        x_indices.push(i);
-                    }
-                }
+                let idx = self.index(x as usize, y as usize);
+                self.scratch[idx] = if self.cells[idx] {
+                    self.rule.survival[neighbour_count]
+                } else {
+                    self.rule.birth[neighbour_count]
+                };
+            }
+        }
 
-                if y != 0 {
-                    let i = y - 1;
-                    if self.height > i as u32 {
-                        y_indices.push(i);
-                    }
-                }
+        std::mem::swap(&mut self.cells, &mut self.scratch);
+    }
 
-                if y != self.height as usize - 1 {
-                    let i = y + 1;
-                    if self.height > i as u32 {
-                        y_indices.push(i);
-                    }
-                }
+    /// Advance the grid until it settles into a fixed point or a periodic oscillation, or until
+    /// `max_gens` generations have passed, whichever comes first. Returns the detected period
+    /// (the number of generations between a state and its next occurrence), `0` for a fixed
+    /// point (a state that is identical to the one immediately before it), or `None` if no
+    /// repeat was found within `max_gens` generations.
+    pub fn advance_until_stable(&mut self, max_gens: usize) -> Option<usize> {
+        let mut seen = HashMap::new();
+        seen.insert(self.fingerprint(), 0);
 
-                for neighbour_x in x_indices {
-                    for &neighbour_y in &y_indices {
-                        if neighbour_x != x || neighbour_y != y {
-                            cell.neighbour_indices.push((neighbour_x, neighbour_y));
-                        }
-                    }
-                }
+        for gen in 1..=max_gens {
+            self.advance();
+            let fingerprint = self.fingerprint();
+            if let Some(&first_seen) = seen.get(&fingerprint) {
+                let period = gen - first_seen;
+                return Some(if period == 1 { 0 } else { period });
             }
+            seen.insert(fingerprint, gen);
         }
+
+        None
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.cells.hash(&mut hasher);
+        hasher.finish()
     }
 
     /// Load an [`Object`] into the grid a the specified position position
     pub fn load_object(&mut self, object: &Object, offset: (usize, usize)) {
         for (x, y) in &object.coordinates {
+            let x = x + offset.0;
+            let y = y + offset.1;
             let error_msg = format!(
                 "Position {:?} is out of bounds for grid of size ({}, {})",
-                (*x + offset.0, *y + offset.1),
+                (x, y),
                 self.width,
                 self.height
             );
-            self.cells
-                .get_mut(*y + offset.1)
-                .expect(&error_msg)
-                .get_mut(*x + offset.0)
-                .expect(&error_msg)
-                .alive = true;
+            assert!(
+                x < self.width as usize && y < self.height as usize,
+                "{error_msg}"
+            );
+            let idx = self.index(x, y);
+            self.cells[idx] = true;
         }
     }
 }
@@ -159,7 +300,84 @@ impl Grid {
 #[cfg(test)]
 pub mod test {
     use super::*;
-    use crate::Object;
+    use crate::{Object, Rule};
+
+    #[test]
+    fn highlife_births_on_six_neighbours() {
+        // (2, 2) has exactly 6 live neighbours; under B36/S23 it is born, unlike under
+        // Conway's B3/S23, which only births on exactly 3.
+        let mut grid = Grid::with_rule(5, 5, Rule::parse("B36/S23").unwrap());
+        for (x, y) in [(1, 1), (2, 1), (3, 1), (1, 2), (3, 2), (1, 3)] {
+            grid.set(x, y, true);
+        }
+        grid.advance();
+        assert_eq!(grid.get(2, 2), Some(true));
+    }
+
+    #[test]
+    fn toroidal_boundary_sees_wrapped_neighbours() {
+        // (3, 0) and (3, 1) are only neighbours of (0, 0) if the right edge wraps around to the
+        // left one; together with (0, 1) that gives (0, 0) exactly 3 live neighbours.
+        let live_cells = [(3, 0), (3, 1), (0, 1)];
+
+        let mut toroidal = Grid::with_boundary(4, 4, Boundary::Toroidal);
+        for (x, y) in live_cells {
+            toroidal.set(x, y, true);
+        }
+        toroidal.advance();
+        assert_eq!(toroidal.get(0, 0), Some(true));
+
+        let mut dead_boundary = Grid::new(4, 4);
+        for (x, y) in live_cells {
+            dead_boundary.set(x, y, true);
+        }
+        dead_boundary.advance();
+        assert_eq!(dead_boundary.get(0, 0), Some(false));
+    }
+
+    #[test]
+    fn toroidal_boundary_does_not_double_count_wrapped_neighbours_on_a_thin_grid() {
+        // On a 2-wide grid, the x-1 and x+1 offsets both wrap to the same physical column, so
+        // (1, 0), (1, 1) and (0, 2) are 3 distinct live neighbours of (0, 1), not 5: double
+        // counting them would mask the birth that B3/S23 requires at exactly 3.
+        let mut grid = Grid::with_boundary(2, 3, Boundary::Toroidal);
+        for (x, y) in [(1, 0), (1, 1), (0, 2)] {
+            grid.set(x, y, true);
+        }
+
+        grid.advance();
+        assert_eq!(grid.get(0, 1), Some(true));
+    }
+
+    #[test]
+    fn get_set_are_bounds_checked() {
+        let mut grid = Grid::new(4, 4);
+
+        assert_eq!(grid.get(1, 1), Some(false));
+        assert_eq!(grid.get(4, 0), None);
+        assert_eq!(grid.get(0, 4), None);
+
+        assert!(grid.set(1, 1, true));
+        assert_eq!(grid.get(1, 1), Some(true));
+        assert!(!grid.set(4, 0, true));
+    }
+
+    #[test]
+    fn indexed_cells_yields_every_coordinate() {
+        let mut grid = Grid::new(2, 2);
+        grid.set(1, 0, true);
+
+        let cells: Vec<_> = grid.indexed_cells().collect();
+        assert_eq!(
+            cells,
+            vec![
+                ((0, 0), false),
+                ((1, 0), true),
+                ((0, 1), false),
+                ((1, 1), false),
+            ]
+        );
+    }
 
     #[test]
     fn empty_grid_advance() {
@@ -173,23 +391,19 @@ pub mod test {
     fn full_grid_advance() {
         let mut grid = Grid::new(8, 8);
         // set all cells to be alive
-        for row in grid.cells.iter_mut() {
-            for cell in row {
-                cell.alive = true;
-            }
+        for cell in grid.cells.iter_mut() {
+            *cell = true;
         }
-        let initial_cells = grid.cells.clone();
         grid.advance();
-        // only corner cells should survice
-        for (y, row) in grid.cells.iter().enumerate() {
-            for (x, cell) in row.iter().enumerate() {
-                if [0, grid.width - 1].contains(&(x as u32))
-                    && [0, grid.height - 1].contains(&(y as u32))
-                {
-                    assert!(cell.alive);
-                } else {
-                    assert!(!cell.alive);
-                }
+        // only corner cells should survive
+        for (i, &alive) in grid.cells.iter().enumerate() {
+            let (x, y) = grid.coords(i);
+            if [0, grid.width - 1].contains(&(x as u32))
+                && [0, grid.height - 1].contains(&(y as u32))
+            {
+                assert!(alive);
+            } else {
+                assert!(!alive);
             }
         }
     }
@@ -200,13 +414,11 @@ pub mod test {
         let glider = Object::from_file("objects/glider.life").expect("Failed to load glider");
         grid.load_object(&glider, (0, 0));
 
-        let alive = vec![(0, 2), (1, 2), (2, 2), (1, 0), (2, 1)];
+        let alive = [(0, 2), (1, 2), (2, 2), (1, 0), (2, 1)];
 
-        for (y, row) in grid.cells.iter_mut().enumerate() {
-            for (x, cell) in row.iter_mut().enumerate() {
-                let coord = (x, y);
-                assert_eq!(cell.alive, alive.contains(&coord));
-            }
+        for (i, &cell) in grid.cells.iter().enumerate() {
+            let coord = grid.coords(i);
+            assert_eq!(cell, alive.contains(&coord));
         }
     }
 
@@ -225,11 +437,9 @@ pub mod test {
 
         grid.print_alive_cells();
 
-        for (y, row) in grid.cells.iter_mut().enumerate() {
-            for (x, cell) in row.iter_mut().enumerate() {
-                let coord = (x, y);
-                assert_eq!(cell.alive, alive.contains(&coord));
-            }
+        for (i, &cell) in grid.cells.iter().enumerate() {
+            let coord = grid.coords(i);
+            assert_eq!(cell, alive.contains(&coord));
         }
     }
 
@@ -240,13 +450,88 @@ pub mod test {
         grid.load_object(&glider, (0, 0));
         grid.advance();
 
-        let alive = vec![(0, 1), (1, 2), (1, 3), (2, 1), (2, 2)];
+        let alive = [(0, 1), (1, 2), (1, 3), (2, 1), (2, 2)];
 
-        for (y, row) in grid.cells.iter_mut().enumerate() {
-            for (x, cell) in row.iter_mut().enumerate() {
-                let coord = (x, y);
-                assert_eq!(cell.alive, alive.contains(&coord));
-            }
+        for (i, &cell) in grid.cells.iter().enumerate() {
+            let coord = grid.coords(i);
+            assert_eq!(cell, alive.contains(&coord));
+        }
+    }
+
+    #[test]
+    fn dying_pattern_stabilises_to_a_fixed_point() {
+        // A single live cell always dies, and an all-dead grid stays all-dead, so the grid
+        // reaches a fixed point (reported as period 0) one generation after the all-dead state
+        // is first reached.
+        let mut grid = Grid::new(4, 4);
+        grid.set(0, 0, true);
+
+        assert_eq!(grid.advance_until_stable(10), Some(0));
+    }
+
+    #[test]
+    fn blinker_oscillates_with_period_two() {
+        let mut grid = Grid::new(5, 5);
+        for (x, y) in [(1, 2), (2, 2), (3, 2)] {
+            grid.set(x, y, true);
         }
+
+        assert_eq!(grid.advance_until_stable(10), Some(2));
+    }
+
+    #[test]
+    fn block_is_a_still_life() {
+        // A block is unchanged by every generation, so it is a fixed point (period 0).
+        let mut grid = Grid::new(4, 4);
+        for (x, y) in [(1, 1), (2, 1), (1, 2), (2, 2)] {
+            grid.set(x, y, true);
+        }
+
+        assert_eq!(grid.advance_until_stable(10), Some(0));
+    }
+
+    #[test]
+    fn zero_generation_budget_finds_no_repeat() {
+        let mut grid = Grid::new(5, 5);
+        grid.set(1, 1, true);
+
+        assert_eq!(grid.advance_until_stable(0), None);
+    }
+
+    /// A 3-state "Brian's Brain"-style automaton, showing `Grid<T>` hosting something other
+    /// than binary Conway cells.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum BrianState {
+        Off,
+        Dying,
+        On,
+    }
+
+    #[test]
+    fn generic_grid_steps_a_non_boolean_automaton() {
+        let mut grid = Grid::with_generator(3, 1, |(x, _)| {
+            if x == 1 {
+                BrianState::On
+            } else {
+                BrianState::Off
+            }
+        });
+
+        grid.step_with(|&state, neighbours| match state {
+            BrianState::On => BrianState::Dying,
+            BrianState::Dying => BrianState::Off,
+            BrianState::Off => {
+                let on_neighbours = neighbours.iter().filter(|&&n| n == BrianState::On).count();
+                if on_neighbours == 2 {
+                    BrianState::On
+                } else {
+                    BrianState::Off
+                }
+            }
+        });
+
+        assert_eq!(grid.get(0, 0), Some(BrianState::Off));
+        assert_eq!(grid.get(1, 0), Some(BrianState::Dying));
+        assert_eq!(grid.get(2, 0), Some(BrianState::Off));
     }
 }
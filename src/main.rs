@@ -14,7 +14,7 @@ use open_oak::{Rad, Rgba, Vector2};
 fn main() {
     let mut grid = Grid::new(16, 16);
 
-    let glider = Object::from_file("objects/glider.life");
+    let glider = Object::from_file("objects/glider.life").expect("failed to load glider");
     grid.load_object(&glider, (0, 0));
     grid.advance();
 
@@ -1,5 +1,7 @@
 //! Module exposing the API for creating custom objects or starting configurations that can be
 //! loaded onto a grid.
+use std::path::Path;
+
 /// Struct representing objects that can be loaded onto the grid.
 /// You can for example load just one object, and then that object represents your entire
 /// initial starting state for the grid, or you can for example have one object that represents
@@ -21,13 +23,18 @@ pub enum LoadObjectError {
 }
 
 impl Object {
-    /// Load an object from a file, usually with a `.life` extension, but this is not required.
-    /// [`Self::from_string`] calls this function under the hood,
-    /// so you can refer to its documentation to see what the format of the string should be.
+    /// Load an object from a file. The format is chosen based on the file's extension:
+    /// plaintext `.cells` and RLE `.rle` files are parsed in their respective standard formats
+    /// (see <https://conwaylife.com/wiki/Plaintext> and <https://conwaylife.com/wiki/Run_Length_Encoded>),
+    /// and anything else falls back to [`Self::from_string`]'s bespoke coordinate list format.
     /// Sample files defining various objects can be found at <https://github.com/christofferaakre/conlife/tree/master/objects>.
     pub fn from_file(filename: &str) -> Result<Object, LoadObjectError> {
         let file_contents = std::fs::read_to_string(filename).expect("Failed to read file");
-        Self::from_string(file_contents.as_str())
+        match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+            Some("cells") => Self::from_plaintext(&file_contents),
+            Some("rle") => Self::from_rle(&file_contents),
+            _ => Self::from_string(&file_contents),
+        }
     }
 
     /// Load an object from a string. The string should contain ordered (x,y) coordinate pairs, separated by whitespace.
@@ -67,6 +74,85 @@ impl Object {
         }
         Ok(Self { coordinates })
     }
+
+    /// Load an object from the plaintext `.cells` format: `.` is a dead cell, `O` or `*` is a
+    /// live cell, and lines starting with `!` are comments. The row index is the line number
+    /// (ignoring comment lines) and the column index is the character's position in the line.
+    fn from_plaintext(buffer: &str) -> Result<Object, LoadObjectError> {
+        let mut coordinates = vec![];
+        let mut y = 0;
+        for line in buffer.lines() {
+            if line.starts_with('!') {
+                continue;
+            }
+            for (x, tile) in line.chars().enumerate() {
+                match tile {
+                    'O' | '*' => coordinates.push((x, y)),
+                    '.' => {}
+                    _ => return Err(LoadObjectError::BadInput),
+                }
+            }
+            y += 1;
+        }
+        if coordinates.is_empty() {
+            return Err(LoadObjectError::NoCoordinatesFound);
+        }
+        Ok(Self { coordinates })
+    }
+
+    /// Load an object from the RLE format: `#`-prefixed comment lines and the `x = .., y = ..`
+    /// header are skipped, and the remaining run-length encoded body is decoded tag by tag,
+    /// where a number prefixes a `b` (dead run), `o` (live run), `$` (end of row) or `!` (end of
+    /// pattern) tag, and an omitted number means a run length of 1.
+    fn from_rle(buffer: &str) -> Result<Object, LoadObjectError> {
+        let mut coordinates = vec![];
+        let mut x = 0;
+        let mut y = 0;
+        let mut run_length = String::new();
+
+        'lines: for line in buffer.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('x') {
+                continue;
+            }
+
+            for tag in line.chars() {
+                match tag {
+                    '0'..='9' => run_length.push(tag),
+                    'b' | 'o' | '$' | '!' => {
+                        let count = if run_length.is_empty() {
+                            1
+                        } else {
+                            run_length
+                                .parse()
+                                .map_err(|_| LoadObjectError::BadInput)?
+                        };
+                        run_length.clear();
+
+                        match tag {
+                            'b' => x += count,
+                            'o' => {
+                                coordinates.extend((x..x + count).map(|x| (x, y)));
+                                x += count;
+                            }
+                            '$' => {
+                                y += count;
+                                x = 0;
+                            }
+                            '!' => break 'lines,
+                            _ => unreachable!(),
+                        }
+                    }
+                    _ => return Err(LoadObjectError::BadInput),
+                }
+            }
+        }
+
+        if coordinates.is_empty() {
+            return Err(LoadObjectError::NoCoordinatesFound);
+        }
+        Ok(Self { coordinates })
+    }
 }
 
 #[cfg(test)]
@@ -107,6 +193,49 @@ mod tests {
         assert_eq!(Err(LoadObjectError::DuplicateCoordinate), object);
     }
 
+    #[test]
+    fn from_plaintext_parses_dots_and_stars() {
+        let buffer = "!comment line\n.O.\n..*\nOOO\n";
+        let object = Object::from_plaintext(buffer);
+        assert!(object.is_ok());
+        assert_eq!(
+            object.unwrap().coordinates,
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn from_plaintext_rejects_unknown_characters() {
+        let object = Object::from_plaintext(".X.\n");
+        assert_eq!(Err(LoadObjectError::BadInput), object);
+    }
+
+    #[test]
+    fn from_rle_decodes_runs() {
+        let buffer = "#comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+        let object = Object::from_rle(buffer);
+        assert!(object.is_ok());
+        assert_eq!(
+            object.unwrap().coordinates,
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn from_file_dispatches_on_extension() {
+        let glider = Object::from_file("objects/glider.cells").unwrap();
+        assert_eq!(
+            glider.coordinates,
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+
+        let glider = Object::from_file("objects/glider.rle").unwrap();
+        assert_eq!(
+            glider.coordinates,
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
     #[test]
     fn load_glider() {
         let glider = Object::from_file("objects/glider.life");
@@ -0,0 +1,120 @@
+//! Module exposing the [`Rule`] type, used to configure which life-like cellular automaton
+//! rule a [`crate::Grid`] evolves under.
+
+/// A life-like rule in B/S (birth/survival) notation: for each neighbour count 0-8, whether a
+/// dead cell with that many live neighbours is born, and whether a live cell with that many live
+/// neighbours survives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+/// Enum describing the possible errors that can happen when trying to parse a [`Rule`].
+#[derive(Debug, PartialEq)]
+pub enum ParseRuleError {
+    /// The rule string did not contain a `/` separating the birth and survival specifiers
+    MissingSlash,
+    /// The birth specifier did not start with `B`
+    InvalidBirthSpecifier,
+    /// The survival specifier did not start with `S`
+    InvalidSurvivalSpecifier,
+    /// A birth or survival specifier contained something other than a digit 0-8
+    InvalidDigit,
+}
+
+impl Rule {
+    /// Conway's Game of Life: B3/S23.
+    pub fn conway() -> Self {
+        Self::parse("B3/S23").expect("B3/S23 is a valid rule string")
+    }
+
+    /// Parse a rule given in standard B/S notation, e.g. `"B3/S23"` for Conway's Game of Life,
+    /// `"B36/S23"` for HighLife, or `"B2/S"` for Seeds.
+    pub fn parse(rule_str: &str) -> Result<Self, ParseRuleError> {
+        let mut parts = rule_str.split('/');
+        let birth_part = parts.next().ok_or(ParseRuleError::MissingSlash)?;
+        let survival_part = parts.next().ok_or(ParseRuleError::MissingSlash)?;
+
+        let birth_digits = birth_part
+            .strip_prefix('B')
+            .ok_or(ParseRuleError::InvalidBirthSpecifier)?;
+        let survival_digits = survival_part
+            .strip_prefix('S')
+            .ok_or(ParseRuleError::InvalidSurvivalSpecifier)?;
+
+        Ok(Self {
+            birth: Self::parse_counts(birth_digits)?,
+            survival: Self::parse_counts(survival_digits)?,
+        })
+    }
+
+    fn parse_counts(digits: &str) -> Result<[bool; 9], ParseRuleError> {
+        let mut counts = [false; 9];
+        for digit in digits.chars() {
+            let n = digit.to_digit(10).ok_or(ParseRuleError::InvalidDigit)? as usize;
+            if n > 8 {
+                return Err(ParseRuleError::InvalidDigit);
+            }
+            counts[n] = true;
+        }
+        Ok(counts)
+    }
+}
+
+impl Default for Rule {
+    /// Defaults to Conway's Game of Life: B3/S23.
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_conway() {
+        let rule = Rule::parse("B3/S23").unwrap();
+        assert_eq!(rule, Rule::conway());
+        assert_eq!(rule, Rule::default());
+    }
+
+    #[test]
+    fn parses_highlife() {
+        let rule = Rule::parse("B36/S23").unwrap();
+        assert!(rule.birth[3] && rule.birth[6]);
+        assert!(rule.survival[2] && rule.survival[3]);
+        assert!(!rule.birth[2] && !rule.birth[4]);
+    }
+
+    #[test]
+    fn parses_seeds_with_empty_survival() {
+        let rule = Rule::parse("B2/S").unwrap();
+        assert!(rule.birth[2]);
+        assert_eq!(rule.survival, [false; 9]);
+    }
+
+    #[test]
+    fn missing_slash_is_an_error() {
+        assert_eq!(Rule::parse("B3S23"), Err(ParseRuleError::MissingSlash));
+    }
+
+    #[test]
+    fn missing_prefix_is_an_error() {
+        assert_eq!(
+            Rule::parse("3/S23"),
+            Err(ParseRuleError::InvalidBirthSpecifier)
+        );
+        assert_eq!(
+            Rule::parse("B3/23"),
+            Err(ParseRuleError::InvalidSurvivalSpecifier)
+        );
+    }
+
+    #[test]
+    fn invalid_digit_is_an_error() {
+        assert_eq!(Rule::parse("B9/S23"), Err(ParseRuleError::InvalidDigit));
+        assert_eq!(Rule::parse("Bx/S23"), Err(ParseRuleError::InvalidDigit));
+    }
+}